@@ -0,0 +1,6 @@
+#[macro_use]
+mod with_shape;
+
+mod registry;
+
+pub use registry::*;
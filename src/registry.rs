@@ -2,15 +2,55 @@ use std::sync::atomic::Ordering;
 
 use anyhow::Result;
 use filecoin_proofs_v1::types::{PoRepConfig, PoRepProofPartitions, PoStConfig, SectorSize};
+use num_derive::FromPrimitive;
+use num_traits::FromPrimitive as _;
+use serde::{Deserialize, Serialize};
 
 /// Available seal proofs.
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+///
+/// This enum is append-only: the numeric code assigned to each variant is
+/// persisted and transmitted by downstream chain/actor code, so existing
+/// variants must never be removed, reordered, or renumbered. Only add new
+/// variants at the end, with the next unused code.
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, FromPrimitive, Serialize, Deserialize)]
 pub enum RegisteredSealProof {
-    StackedDrg1KiBV1,
-    StackedDrg16MiBV1,
-    StackedDrg256MiBV1,
-    StackedDrg1GiBV1,
-    StackedDrg32GiBV1,
+    StackedDrg1KiBV1 = 0,
+    StackedDrg16MiBV1 = 1,
+    StackedDrg256MiBV1 = 2,
+    StackedDrg1GiBV1 = 3,
+    StackedDrg32GiBV1 = 4,
+    StackedDrg1KiBV1_1 = 5,
+    StackedDrg16MiBV1_1 = 6,
+    StackedDrg256MiBV1_1 = 7,
+    StackedDrg1GiBV1_1 = 8,
+    StackedDrg32GiBV1_1 = 9,
+    StackedDrg2KiBV1 = 10,
+    StackedDrg4KiBV1 = 11,
+    StackedDrg16KiBV1 = 12,
+    StackedDrg32KiBV1 = 13,
+    StackedDrg8MiBV1 = 14,
+    StackedDrg512MiBV1 = 15,
+    StackedDrg64GiBV1 = 16,
+    StackedDrg2KiBV1_1 = 17,
+    StackedDrg4KiBV1_1 = 18,
+    StackedDrg16KiBV1_1 = 19,
+    StackedDrg32KiBV1_1 = 20,
+    StackedDrg8MiBV1_1 = 21,
+    StackedDrg512MiBV1_1 = 22,
+    StackedDrg64GiBV1_1 = 23,
+    StackedDrg1KiBV1_1_Ni = 24,
+    StackedDrg2KiBV1_1_Ni = 25,
+    StackedDrg4KiBV1_1_Ni = 26,
+    StackedDrg16KiBV1_1_Ni = 27,
+    StackedDrg32KiBV1_1_Ni = 28,
+    StackedDrg8MiBV1_1_Ni = 29,
+    StackedDrg16MiBV1_1_Ni = 30,
+    StackedDrg256MiBV1_1_Ni = 31,
+    StackedDrg512MiBV1_1_Ni = 32,
+    StackedDrg1GiBV1_1_Ni = 33,
+    StackedDrg32GiBV1_1_Ni = 34,
+    StackedDrg64GiBV1_1_Ni = 35,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
@@ -18,63 +58,230 @@ pub enum Version {
     V1,
 }
 
+/// Distinguishes the graph construction a `RegisteredSealProof` uses.
+///
+/// `V1` is the original, frozen construction. `V1_1` is the updated graph
+/// construction rolled out on a later network version; it is selected via
+/// the proof's [`RegisteredSealProof::porep_id`] rather than a process-wide
+/// default, so a node can service sectors sealed under either version at
+/// the same time.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ApiVersion {
+    V1,
+    V1_1,
+}
+
 impl RegisteredSealProof {
     /// Return the version for this proof.
     pub fn version(self) -> Version {
         use RegisteredSealProof::*;
 
         match self {
-            StackedDrg1KiBV1 | StackedDrg16MiBV1 | StackedDrg256MiBV1 | StackedDrg1GiBV1
-            | StackedDrg32GiBV1 => Version::V1,
+            StackedDrg1KiBV1
+            | StackedDrg16MiBV1
+            | StackedDrg256MiBV1
+            | StackedDrg1GiBV1
+            | StackedDrg32GiBV1
+            | StackedDrg1KiBV1_1
+            | StackedDrg16MiBV1_1
+            | StackedDrg256MiBV1_1
+            | StackedDrg1GiBV1_1
+            | StackedDrg32GiBV1_1
+            | StackedDrg2KiBV1
+            | StackedDrg4KiBV1
+            | StackedDrg16KiBV1
+            | StackedDrg32KiBV1
+            | StackedDrg8MiBV1
+            | StackedDrg512MiBV1
+            | StackedDrg64GiBV1
+            | StackedDrg2KiBV1_1
+            | StackedDrg4KiBV1_1
+            | StackedDrg16KiBV1_1
+            | StackedDrg32KiBV1_1
+            | StackedDrg8MiBV1_1
+            | StackedDrg512MiBV1_1
+            | StackedDrg64GiBV1_1
+            | StackedDrg1KiBV1_1_Ni
+            | StackedDrg2KiBV1_1_Ni
+            | StackedDrg4KiBV1_1_Ni
+            | StackedDrg16KiBV1_1_Ni
+            | StackedDrg32KiBV1_1_Ni
+            | StackedDrg8MiBV1_1_Ni
+            | StackedDrg16MiBV1_1_Ni
+            | StackedDrg256MiBV1_1_Ni
+            | StackedDrg512MiBV1_1_Ni
+            | StackedDrg1GiBV1_1_Ni
+            | StackedDrg32GiBV1_1_Ni
+            | StackedDrg64GiBV1_1_Ni => Version::V1,
         }
     }
 
+    /// Return the API version for this proof, i.e. which graph construction
+    /// it uses.
+    pub fn api_version(self) -> ApiVersion {
+        use RegisteredSealProof::*;
+
+        match self {
+            StackedDrg1KiBV1
+            | StackedDrg16MiBV1
+            | StackedDrg256MiBV1
+            | StackedDrg1GiBV1
+            | StackedDrg32GiBV1
+            | StackedDrg2KiBV1
+            | StackedDrg4KiBV1
+            | StackedDrg16KiBV1
+            | StackedDrg32KiBV1
+            | StackedDrg8MiBV1
+            | StackedDrg512MiBV1
+            | StackedDrg64GiBV1 => ApiVersion::V1,
+            StackedDrg1KiBV1_1
+            | StackedDrg16MiBV1_1
+            | StackedDrg256MiBV1_1
+            | StackedDrg1GiBV1_1
+            | StackedDrg32GiBV1_1
+            | StackedDrg2KiBV1_1
+            | StackedDrg4KiBV1_1
+            | StackedDrg16KiBV1_1
+            | StackedDrg32KiBV1_1
+            | StackedDrg8MiBV1_1
+            | StackedDrg512MiBV1_1
+            | StackedDrg64GiBV1_1
+            | StackedDrg1KiBV1_1_Ni
+            | StackedDrg2KiBV1_1_Ni
+            | StackedDrg4KiBV1_1_Ni
+            | StackedDrg16KiBV1_1_Ni
+            | StackedDrg32KiBV1_1_Ni
+            | StackedDrg8MiBV1_1_Ni
+            | StackedDrg16MiBV1_1_Ni
+            | StackedDrg256MiBV1_1_Ni
+            | StackedDrg512MiBV1_1_Ni
+            | StackedDrg1GiBV1_1_Ni
+            | StackedDrg32GiBV1_1_Ni
+            | StackedDrg64GiBV1_1_Ni => ApiVersion::V1_1,
+        }
+    }
+
+    /// Returns whether this proof's challenge set is derived
+    /// non-interactively (via Fiat–Shamir from the replica commitment and a
+    /// chain-supplied seed) rather than from an interactive on-chain
+    /// PreCommit/ProveCommit randomness round.
+    ///
+    /// Sealing pipelines can use this to skip waiting on that randomness
+    /// round entirely.
+    pub fn is_non_interactive(self) -> bool {
+        use RegisteredSealProof::*;
+
+        matches!(
+            self,
+            StackedDrg1KiBV1_1_Ni
+                | StackedDrg2KiBV1_1_Ni
+                | StackedDrg4KiBV1_1_Ni
+                | StackedDrg16KiBV1_1_Ni
+                | StackedDrg32KiBV1_1_Ni
+                | StackedDrg8MiBV1_1_Ni
+                | StackedDrg16MiBV1_1_Ni
+                | StackedDrg256MiBV1_1_Ni
+                | StackedDrg512MiBV1_1_Ni
+                | StackedDrg1GiBV1_1_Ni
+                | StackedDrg32GiBV1_1_Ni
+                | StackedDrg64GiBV1_1_Ni
+        )
+    }
+
     /// Return the sector size for this proof.
     pub fn sector_size(self) -> SectorSize {
         use filecoin_proofs_v1::constants;
         use RegisteredSealProof::*;
         let size = match self {
-            StackedDrg1KiBV1 => constants::SECTOR_SIZE_ONE_KIB,
-            StackedDrg16MiBV1 => constants::SECTOR_SIZE_16_MIB,
-            StackedDrg256MiBV1 => constants::SECTOR_SIZE_256_MIB,
-            StackedDrg1GiBV1 => constants::SECTOR_SIZE_1_GIB,
-            StackedDrg32GiBV1 => constants::SECTOR_SIZE_32_GIB,
+            StackedDrg1KiBV1 | StackedDrg1KiBV1_1 | StackedDrg1KiBV1_1_Ni => {
+                constants::SECTOR_SIZE_ONE_KIB
+            }
+            StackedDrg2KiBV1 | StackedDrg2KiBV1_1 | StackedDrg2KiBV1_1_Ni => {
+                constants::SECTOR_SIZE_2_KIB
+            }
+            StackedDrg4KiBV1 | StackedDrg4KiBV1_1 | StackedDrg4KiBV1_1_Ni => {
+                constants::SECTOR_SIZE_4_KIB
+            }
+            StackedDrg16KiBV1 | StackedDrg16KiBV1_1 | StackedDrg16KiBV1_1_Ni => {
+                constants::SECTOR_SIZE_16_KIB
+            }
+            StackedDrg32KiBV1 | StackedDrg32KiBV1_1 | StackedDrg32KiBV1_1_Ni => {
+                constants::SECTOR_SIZE_32_KIB
+            }
+            StackedDrg8MiBV1 | StackedDrg8MiBV1_1 | StackedDrg8MiBV1_1_Ni => {
+                constants::SECTOR_SIZE_8_MIB
+            }
+            StackedDrg16MiBV1 | StackedDrg16MiBV1_1 | StackedDrg16MiBV1_1_Ni => {
+                constants::SECTOR_SIZE_16_MIB
+            }
+            StackedDrg256MiBV1 | StackedDrg256MiBV1_1 | StackedDrg256MiBV1_1_Ni => {
+                constants::SECTOR_SIZE_256_MIB
+            }
+            StackedDrg512MiBV1 | StackedDrg512MiBV1_1 | StackedDrg512MiBV1_1_Ni => {
+                constants::SECTOR_SIZE_512_MIB
+            }
+            StackedDrg1GiBV1 | StackedDrg1GiBV1_1 | StackedDrg1GiBV1_1_Ni => {
+                constants::SECTOR_SIZE_1_GIB
+            }
+            StackedDrg32GiBV1 | StackedDrg32GiBV1_1 | StackedDrg32GiBV1_1_Ni => {
+                constants::SECTOR_SIZE_32_GIB
+            }
+            StackedDrg64GiBV1 | StackedDrg64GiBV1_1 | StackedDrg64GiBV1_1_Ni => {
+                constants::SECTOR_SIZE_64_GIB
+            }
         };
         SectorSize(size)
     }
 
     /// Return the number of partitions for this proof.
+    ///
+    /// Non-interactive variants forgo the interactive on-chain randomness
+    /// round, so they need a larger challenge set spread across more
+    /// partitions to keep the same soundness.
     pub fn partitions(self) -> u8 {
-        use RegisteredSealProof::*;
-
-        match self {
-            StackedDrg1KiBV1 | StackedDrg16MiBV1 | StackedDrg256MiBV1 | StackedDrg1GiBV1
-            | StackedDrg32GiBV1 => filecoin_proofs_v1::constants::DEFAULT_POREP_PROOF_PARTITIONS
-                .load(Ordering::Relaxed),
+        if self.is_non_interactive() {
+            filecoin_proofs_v1::constants::NI_POREP_PROOF_PARTITIONS
+        } else {
+            filecoin_proofs_v1::constants::DEFAULT_POREP_PROOF_PARTITIONS.load(Ordering::Relaxed)
         }
     }
 
     pub fn single_partition_proof_len(self) -> usize {
-        use RegisteredSealProof::*;
+        filecoin_proofs_v1::SINGLE_PARTITION_PROOF_LEN
+    }
 
-        match self {
-            StackedDrg1KiBV1 | StackedDrg16MiBV1 | StackedDrg256MiBV1 | StackedDrg1GiBV1
-            | StackedDrg32GiBV1 => filecoin_proofs_v1::SINGLE_PARTITION_PROOF_LEN,
+    /// Return the 32-byte `porep_id` identifying the graph this proof was
+    /// built over.
+    ///
+    /// The id is derived from the sector size, [`ApiVersion`], and whether
+    /// the proof is non-interactive, rather than read from a process-global
+    /// default, so `as_v1_config` selects the correct graph for a sector
+    /// sealed under any of these without mutating any process-global state.
+    /// (The partition count of interactive proofs is still read from
+    /// `DEFAULT_POREP_PROOF_PARTITIONS`; see [`RegisteredSealProof::partitions`].)
+    pub fn porep_id(self) -> [u8; 32] {
+        let mut porep_id = [0u8; 32];
+        let sector_size: u64 = self.sector_size().0;
+
+        porep_id[..8].copy_from_slice(&sector_size.to_le_bytes());
+        if self.api_version() == ApiVersion::V1_1 {
+            porep_id[8] = 1;
+        }
+        if self.is_non_interactive() {
+            porep_id[9] = 1;
         }
+
+        porep_id
     }
 
     pub fn as_v1_config(self) -> PoRepConfig {
-        use RegisteredSealProof::*;
-
         assert_eq!(self.version(), Version::V1);
 
-        match self {
-            StackedDrg1KiBV1 | StackedDrg16MiBV1 | StackedDrg256MiBV1 | StackedDrg1GiBV1
-            | StackedDrg32GiBV1 => PoRepConfig {
-                sector_size: self.sector_size(),
-                partitions: PoRepProofPartitions(self.partitions()),
-            },
-            // _ => panic!("Can only be called on V1 configs"),
+        PoRepConfig {
+            sector_size: self.sector_size(),
+            partitions: PoRepProofPartitions(self.partitions()),
+            porep_id: self.porep_id(),
+            api_version: self.api_version(),
         }
     }
 
@@ -84,16 +291,213 @@ impl RegisteredSealProof {
             Version::V1 => self.as_v1_config().get_cache_identifier(),
         }
     }
+
+    /// Returns the canonical numeric code for this variant, as persisted by
+    /// downstream chain/actor code.
+    pub fn to_u64(self) -> u64 {
+        self as u64
+    }
+
+    /// Recovers a variant from its canonical numeric code, as returned by
+    /// [`RegisteredSealProof::to_u64`].
+    pub fn from_u64(code: u64) -> Option<Self> {
+        FromPrimitive::from_u64(code)
+    }
+
+    /// Returns the `SetupParams` for this proof, monomorphized over the
+    /// `MerkleTreeTrait` shape matching its sector size.
+    ///
+    /// Unlike `cache_identifier`, this genuinely depends on `Tree`, so it is
+    /// the operation shape-dispatch exists for: without it, every caller
+    /// would have to match on `sector_size()` itself to pick the right
+    /// `SectorShape*` before calling into `filecoin_proofs_v1::parameters`.
+    pub fn setup_params(self) -> Result<filecoin_proofs_v1::parameter_cache::SetupParams> {
+        fn generic<Tree: 'static + filecoin_proofs_v1::types::MerkleTreeTrait>(
+            proof: RegisteredSealProof,
+        ) -> Result<filecoin_proofs_v1::parameter_cache::SetupParams> {
+            filecoin_proofs_v1::parameters::setup_params::<Tree>(&proof.as_v1_config())
+        }
+
+        self_shape!(self, generic, self)
+    }
+
+    /// Verify a Groth16 seal (PoRep) proof for a sector of this
+    /// `RegisteredSealProof`'s shape, without the caller having to match on
+    /// `sector_size()` to pick a `MerkleTreeTrait` first.
+    #[allow(clippy::too_many_arguments)]
+    pub fn verify_seal(
+        self,
+        comm_r_in: filecoin_proofs_v1::types::Commitment,
+        comm_d_in: filecoin_proofs_v1::types::Commitment,
+        prover_id: filecoin_proofs_v1::types::ProverId,
+        sector_id: filecoin_proofs_v1::types::SectorId,
+        ticket: filecoin_proofs_v1::types::Ticket,
+        seed: filecoin_proofs_v1::types::Ticket,
+        proof_vec: &[u8],
+    ) -> Result<bool> {
+        #[allow(clippy::too_many_arguments)]
+        fn generic<Tree: 'static + filecoin_proofs_v1::types::MerkleTreeTrait>(
+            proof: RegisteredSealProof,
+            comm_r_in: filecoin_proofs_v1::types::Commitment,
+            comm_d_in: filecoin_proofs_v1::types::Commitment,
+            prover_id: filecoin_proofs_v1::types::ProverId,
+            sector_id: filecoin_proofs_v1::types::SectorId,
+            ticket: filecoin_proofs_v1::types::Ticket,
+            seed: filecoin_proofs_v1::types::Ticket,
+            proof_vec: &[u8],
+        ) -> Result<bool> {
+            filecoin_proofs_v1::verify_seal::<Tree>(
+                &proof.as_v1_config(),
+                comm_r_in,
+                comm_d_in,
+                prover_id,
+                sector_id,
+                ticket,
+                seed,
+                proof_vec,
+            )
+        }
+
+        self_shape!(
+            self,
+            generic,
+            self,
+            comm_r_in,
+            comm_d_in,
+            prover_id,
+            sector_id,
+            ticket,
+            seed,
+            proof_vec
+        )
+    }
 }
 
-/// Available seal proofs.
+/// Available proof-aggregation schemes (SnarkPack) for batching many
+/// individual Groth16 seal proofs into a single, logarithmically-sized
+/// proof.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RegisteredAggregationProof {
+    SnarkPackV1,
+}
+
+/// Selects the SRS (structured reference string) transcript that an
+/// aggregated proof was produced against.
+///
+/// This is pinned independently of the individual seal proof's [`Version`]
+/// so that the transcript a given aggregation was computed under can be
+/// identified without re-deriving it from the proof bytes.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AggregateVersion {
+    V1,
+}
+
+impl RegisteredAggregationProof {
+    /// Return the version for this proof.
+    pub fn version(self) -> Version {
+        match self {
+            RegisteredAggregationProof::SnarkPackV1 => Version::V1,
+        }
+    }
+
+    /// Return the aggregation (SRS transcript) version for this proof.
+    pub fn aggregate_version(self) -> AggregateVersion {
+        match self {
+            RegisteredAggregationProof::SnarkPackV1 => AggregateVersion::V1,
+        }
+    }
+
+    /// Returns the cache identifier for the SRS backing this aggregation
+    /// scheme.
+    pub fn cache_identifier(self) -> Result<String> {
+        match self {
+            RegisteredAggregationProof::SnarkPackV1 => Ok(String::from("snark_pack_v1")),
+        }
+    }
+}
+
+/// Aggregate a batch of individual Groth16 seal (PoRep) proofs produced
+/// under `seal_proof` into a single SnarkPack proof whose size grows only
+/// with log(N), where N is the number of proofs being aggregated.
+///
+/// `commit_outputs` are the raw, serialized single-partition Groth16 proofs
+/// (the `A`, `B`, `C` triples), in the order the corresponding sectors were
+/// proven.
+pub fn aggregate_seal_commit_proofs(
+    seal_proof: RegisteredSealProof,
+    aggregate_proof: RegisteredAggregationProof,
+    commit_outputs: &[Vec<u8>],
+) -> Result<Vec<u8>> {
+    assert_eq!(seal_proof.version(), Version::V1);
+    assert_eq!(aggregate_proof.version(), Version::V1);
+
+    filecoin_proofs_v1::aggregate_seal_commit_proofs(&seal_proof.as_v1_config(), commit_outputs)
+}
+
+/// Verify a SnarkPack-aggregated proof produced by
+/// [`aggregate_seal_commit_proofs`].
+pub fn verify_aggregate_seal_commit_proofs(
+    seal_proof: RegisteredSealProof,
+    aggregate_proof: RegisteredAggregationProof,
+    aggregate_proof_bytes: &[u8],
+    comm_inputs: Vec<[u8; 32]>,
+) -> Result<bool> {
+    assert_eq!(seal_proof.version(), Version::V1);
+    assert_eq!(aggregate_proof.version(), Version::V1);
+
+    filecoin_proofs_v1::verify_aggregate_seal_commit_proofs(
+        &seal_proof.as_v1_config(),
+        aggregate_proof_bytes,
+        comm_inputs,
+    )
+}
+
+/// Distinguishes the two kinds of Proof-of-Spacetime the protocol uses.
+///
+/// Winning PoSt is run once per election, over a single randomly-chosen
+/// sector, and must verify quickly on-chain, so it uses a small challenge
+/// count and a single partition. Window PoSt is run periodically over a
+/// miner's whole set of active sectors and tolerates a much larger,
+/// multi-partition challenge set in exchange for stronger guarantees.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PoStType {
+    Winning,
+    Window,
+}
+
+/// Available PoSt proofs.
+///
+/// This enum is append-only: the numeric code assigned to each variant is
+/// persisted and transmitted by downstream chain/actor code, so existing
+/// variants must never be removed, reordered, or renumbered. Only add new
+/// variants at the end, with the next unused code.
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, FromPrimitive, Serialize, Deserialize)]
 pub enum RegisteredPoStProof {
-    StackedDrg1KiBV1,
-    StackedDrg16MiBV1,
-    StackedDrg256MiBV1,
-    StackedDrg1GiBV1,
-    StackedDrg32GiBV1,
+    StackedDrgWinning1KiBV1 = 0,
+    StackedDrgWinning16MiBV1 = 1,
+    StackedDrgWinning256MiBV1 = 2,
+    StackedDrgWinning1GiBV1 = 3,
+    StackedDrgWinning32GiBV1 = 4,
+    StackedDrgWindow1KiBV1 = 5,
+    StackedDrgWindow16MiBV1 = 6,
+    StackedDrgWindow256MiBV1 = 7,
+    StackedDrgWindow1GiBV1 = 8,
+    StackedDrgWindow32GiBV1 = 9,
+    StackedDrgWinning2KiBV1 = 10,
+    StackedDrgWinning4KiBV1 = 11,
+    StackedDrgWinning16KiBV1 = 12,
+    StackedDrgWinning32KiBV1 = 13,
+    StackedDrgWinning8MiBV1 = 14,
+    StackedDrgWinning512MiBV1 = 15,
+    StackedDrgWinning64GiBV1 = 16,
+    StackedDrgWindow2KiBV1 = 17,
+    StackedDrgWindow4KiBV1 = 18,
+    StackedDrgWindow16KiBV1 = 19,
+    StackedDrgWindow32KiBV1 = 20,
+    StackedDrgWindow8MiBV1 = 21,
+    StackedDrgWindow512MiBV1 = 22,
+    StackedDrgWindow64GiBV1 = 23,
 }
 
 impl RegisteredPoStProof {
@@ -102,8 +506,62 @@ impl RegisteredPoStProof {
         use RegisteredPoStProof::*;
 
         match self {
-            StackedDrg1KiBV1 | StackedDrg16MiBV1 | StackedDrg256MiBV1 | StackedDrg1GiBV1
-            | StackedDrg32GiBV1 => Version::V1,
+            StackedDrgWinning1KiBV1
+            | StackedDrgWinning16MiBV1
+            | StackedDrgWinning256MiBV1
+            | StackedDrgWinning1GiBV1
+            | StackedDrgWinning32GiBV1
+            | StackedDrgWindow1KiBV1
+            | StackedDrgWindow16MiBV1
+            | StackedDrgWindow256MiBV1
+            | StackedDrgWindow1GiBV1
+            | StackedDrgWindow32GiBV1
+            | StackedDrgWinning2KiBV1
+            | StackedDrgWinning4KiBV1
+            | StackedDrgWinning16KiBV1
+            | StackedDrgWinning32KiBV1
+            | StackedDrgWinning8MiBV1
+            | StackedDrgWinning512MiBV1
+            | StackedDrgWinning64GiBV1
+            | StackedDrgWindow2KiBV1
+            | StackedDrgWindow4KiBV1
+            | StackedDrgWindow16KiBV1
+            | StackedDrgWindow32KiBV1
+            | StackedDrgWindow8MiBV1
+            | StackedDrgWindow512MiBV1
+            | StackedDrgWindow64GiBV1 => Version::V1,
+        }
+    }
+
+    /// Return the Winning/Window type for this proof.
+    pub fn typ(self) -> PoStType {
+        use RegisteredPoStProof::*;
+
+        match self {
+            StackedDrgWinning1KiBV1
+            | StackedDrgWinning16MiBV1
+            | StackedDrgWinning256MiBV1
+            | StackedDrgWinning1GiBV1
+            | StackedDrgWinning32GiBV1
+            | StackedDrgWinning2KiBV1
+            | StackedDrgWinning4KiBV1
+            | StackedDrgWinning16KiBV1
+            | StackedDrgWinning32KiBV1
+            | StackedDrgWinning8MiBV1
+            | StackedDrgWinning512MiBV1
+            | StackedDrgWinning64GiBV1 => PoStType::Winning,
+            StackedDrgWindow1KiBV1
+            | StackedDrgWindow16MiBV1
+            | StackedDrgWindow256MiBV1
+            | StackedDrgWindow1GiBV1
+            | StackedDrgWindow32GiBV1
+            | StackedDrgWindow2KiBV1
+            | StackedDrgWindow4KiBV1
+            | StackedDrgWindow16KiBV1
+            | StackedDrgWindow32KiBV1
+            | StackedDrgWindow8MiBV1
+            | StackedDrgWindow512MiBV1
+            | StackedDrgWindow64GiBV1 => PoStType::Window,
         }
     }
 
@@ -113,47 +571,56 @@ impl RegisteredPoStProof {
         use RegisteredPoStProof::*;
 
         let size = match self {
-            StackedDrg1KiBV1 => constants::SECTOR_SIZE_ONE_KIB,
-            StackedDrg16MiBV1 => constants::SECTOR_SIZE_16_MIB,
-            StackedDrg256MiBV1 => constants::SECTOR_SIZE_256_MIB,
-            StackedDrg1GiBV1 => constants::SECTOR_SIZE_1_GIB,
-            StackedDrg32GiBV1 => constants::SECTOR_SIZE_32_GIB,
+            StackedDrgWinning1KiBV1 | StackedDrgWindow1KiBV1 => constants::SECTOR_SIZE_ONE_KIB,
+            StackedDrgWinning2KiBV1 | StackedDrgWindow2KiBV1 => constants::SECTOR_SIZE_2_KIB,
+            StackedDrgWinning4KiBV1 | StackedDrgWindow4KiBV1 => constants::SECTOR_SIZE_4_KIB,
+            StackedDrgWinning16KiBV1 | StackedDrgWindow16KiBV1 => constants::SECTOR_SIZE_16_KIB,
+            StackedDrgWinning32KiBV1 | StackedDrgWindow32KiBV1 => constants::SECTOR_SIZE_32_KIB,
+            StackedDrgWinning8MiBV1 | StackedDrgWindow8MiBV1 => constants::SECTOR_SIZE_8_MIB,
+            StackedDrgWinning16MiBV1 | StackedDrgWindow16MiBV1 => constants::SECTOR_SIZE_16_MIB,
+            StackedDrgWinning256MiBV1 | StackedDrgWindow256MiBV1 => {
+                constants::SECTOR_SIZE_256_MIB
+            }
+            StackedDrgWinning512MiBV1 | StackedDrgWindow512MiBV1 => {
+                constants::SECTOR_SIZE_512_MIB
+            }
+            StackedDrgWinning1GiBV1 | StackedDrgWindow1GiBV1 => constants::SECTOR_SIZE_1_GIB,
+            StackedDrgWinning32GiBV1 | StackedDrgWindow32GiBV1 => constants::SECTOR_SIZE_32_GIB,
+            StackedDrgWinning64GiBV1 | StackedDrgWindow64GiBV1 => constants::SECTOR_SIZE_64_GIB,
         };
         SectorSize(size)
     }
 
     /// Return the number of partitions for this proof.
     pub fn partitions(self) -> u8 {
-        use RegisteredPoStProof::*;
-
-        match self {
-            StackedDrg1KiBV1 | StackedDrg16MiBV1 | StackedDrg256MiBV1 | StackedDrg1GiBV1
-            | StackedDrg32GiBV1 => 1,
+        match self.typ() {
+            PoStType::Winning => 1,
+            PoStType::Window => filecoin_proofs_v1::constants::WINDOW_POST_PARTITIONS,
         }
     }
 
     pub fn single_partition_proof_len(self) -> usize {
-        use RegisteredPoStProof::*;
-
-        match self {
-            StackedDrg1KiBV1 | StackedDrg16MiBV1 | StackedDrg256MiBV1 | StackedDrg1GiBV1
-            | StackedDrg32GiBV1 => filecoin_proofs_v1::SINGLE_PARTITION_PROOF_LEN,
-        }
+        filecoin_proofs_v1::SINGLE_PARTITION_PROOF_LEN
     }
 
     pub fn as_v1_config(self) -> PoStConfig {
         assert_eq!(self.version(), Version::V1);
 
-        use RegisteredPoStProof::*;
+        let (challenge_count, challenged_nodes) = match self.typ() {
+            PoStType::Winning => (
+                filecoin_proofs_v1::constants::WINNING_POST_CHALLENGE_COUNT,
+                filecoin_proofs_v1::constants::WINNING_POST_CHALLENGED_NODES,
+            ),
+            PoStType::Window => (
+                filecoin_proofs_v1::constants::WINDOW_POST_CHALLENGE_COUNT,
+                filecoin_proofs_v1::constants::WINDOW_POST_CHALLENGED_NODES,
+            ),
+        };
 
-        match self {
-            StackedDrg1KiBV1 | StackedDrg16MiBV1 | StackedDrg256MiBV1 | StackedDrg1GiBV1
-            | StackedDrg32GiBV1 => PoStConfig {
-                sector_size: self.sector_size(),
-                challenge_count: filecoin_proofs_v1::constants::POST_CHALLENGE_COUNT,
-                challenged_nodes: filecoin_proofs_v1::constants::POST_CHALLENGED_NODES,
-            },
-            // _ => panic!("Can only be called on V1 configs"),
+        PoStConfig {
+            sector_size: self.sector_size(),
+            challenge_count,
+            challenged_nodes,
         }
     }
 
@@ -163,4 +630,230 @@ impl RegisteredPoStProof {
             Version::V1 => self.as_v1_config().get_cache_identifier(),
         }
     }
+
+    /// Returns the canonical numeric code for this variant, as persisted by
+    /// downstream chain/actor code.
+    pub fn to_u64(self) -> u64 {
+        self as u64
+    }
+
+    /// Recovers a variant from its canonical numeric code, as returned by
+    /// [`RegisteredPoStProof::to_u64`].
+    pub fn from_u64(code: u64) -> Option<Self> {
+        FromPrimitive::from_u64(code)
+    }
+
+    /// Returns the `SetupParams` for this proof, monomorphized over the
+    /// `MerkleTreeTrait` shape matching its sector size.
+    ///
+    /// Unlike `cache_identifier`, this genuinely depends on `Tree`, so it is
+    /// the operation shape-dispatch exists for: without it, every caller
+    /// would have to match on `sector_size()` itself to pick the right
+    /// `SectorShape*` before calling into `filecoin_proofs_v1::parameters`.
+    pub fn setup_params(self) -> Result<filecoin_proofs_v1::parameter_cache::SetupParams> {
+        fn generic<Tree: 'static + filecoin_proofs_v1::types::MerkleTreeTrait>(
+            proof: RegisteredPoStProof,
+        ) -> Result<filecoin_proofs_v1::parameter_cache::SetupParams> {
+            filecoin_proofs_v1::parameters::setup_params::<Tree>(&proof.as_v1_config())
+        }
+
+        self_shape!(self, generic, self)
+    }
+
+    /// Verify a PoSt proof for this `RegisteredPoStProof`'s shape, without
+    /// the caller having to match on `sector_size()` to pick a
+    /// `MerkleTreeTrait`, or on `typ()` to pick Winning vs. Window
+    /// verification.
+    pub fn verify_post(
+        self,
+        randomness: filecoin_proofs_v1::types::ChallengeSeed,
+        prover_id: filecoin_proofs_v1::types::ProverId,
+        replicas: &std::collections::BTreeMap<
+            filecoin_proofs_v1::types::SectorId,
+            filecoin_proofs_v1::types::PublicReplicaInfo,
+        >,
+        proof: &[u8],
+    ) -> Result<bool> {
+        fn generic<Tree: 'static + filecoin_proofs_v1::types::MerkleTreeTrait>(
+            post_proof: RegisteredPoStProof,
+            randomness: filecoin_proofs_v1::types::ChallengeSeed,
+            prover_id: filecoin_proofs_v1::types::ProverId,
+            replicas: &std::collections::BTreeMap<
+                filecoin_proofs_v1::types::SectorId,
+                filecoin_proofs_v1::types::PublicReplicaInfo,
+            >,
+            proof: &[u8],
+        ) -> Result<bool> {
+            let config = post_proof.as_v1_config();
+
+            match post_proof.typ() {
+                PoStType::Winning => {
+                    filecoin_proofs_v1::verify_winning_post::<Tree>(
+                        &config, &randomness, replicas, prover_id, proof,
+                    )
+                }
+                PoStType::Window => {
+                    filecoin_proofs_v1::verify_window_post::<Tree>(
+                        &config, &randomness, replicas, prover_id, proof,
+                    )
+                }
+            }
+        }
+
+        self_shape!(self, generic, self, randomness, prover_id, replicas, proof)
+    }
+}
+
+impl From<RegisteredSealProof> for RegisteredPoStProof {
+    /// Maps a sector's seal proof to the (Window) PoSt proof it must
+    /// satisfy.
+    fn from(other: RegisteredSealProof) -> Self {
+        use RegisteredSealProof::*;
+
+        match other {
+            StackedDrg1KiBV1 | StackedDrg1KiBV1_1 | StackedDrg1KiBV1_1_Ni => {
+                RegisteredPoStProof::StackedDrgWindow1KiBV1
+            }
+            StackedDrg16MiBV1 | StackedDrg16MiBV1_1 | StackedDrg16MiBV1_1_Ni => {
+                RegisteredPoStProof::StackedDrgWindow16MiBV1
+            }
+            StackedDrg256MiBV1 | StackedDrg256MiBV1_1 | StackedDrg256MiBV1_1_Ni => {
+                RegisteredPoStProof::StackedDrgWindow256MiBV1
+            }
+            StackedDrg1GiBV1 | StackedDrg1GiBV1_1 | StackedDrg1GiBV1_1_Ni => {
+                RegisteredPoStProof::StackedDrgWindow1GiBV1
+            }
+            StackedDrg32GiBV1 | StackedDrg32GiBV1_1 | StackedDrg32GiBV1_1_Ni => {
+                RegisteredPoStProof::StackedDrgWindow32GiBV1
+            }
+            StackedDrg2KiBV1 | StackedDrg2KiBV1_1 | StackedDrg2KiBV1_1_Ni => {
+                RegisteredPoStProof::StackedDrgWindow2KiBV1
+            }
+            StackedDrg4KiBV1 | StackedDrg4KiBV1_1 | StackedDrg4KiBV1_1_Ni => {
+                RegisteredPoStProof::StackedDrgWindow4KiBV1
+            }
+            StackedDrg16KiBV1 | StackedDrg16KiBV1_1 | StackedDrg16KiBV1_1_Ni => {
+                RegisteredPoStProof::StackedDrgWindow16KiBV1
+            }
+            StackedDrg32KiBV1 | StackedDrg32KiBV1_1 | StackedDrg32KiBV1_1_Ni => {
+                RegisteredPoStProof::StackedDrgWindow32KiBV1
+            }
+            StackedDrg8MiBV1 | StackedDrg8MiBV1_1 | StackedDrg8MiBV1_1_Ni => {
+                RegisteredPoStProof::StackedDrgWindow8MiBV1
+            }
+            StackedDrg512MiBV1 | StackedDrg512MiBV1_1 | StackedDrg512MiBV1_1_Ni => {
+                RegisteredPoStProof::StackedDrgWindow512MiBV1
+            }
+            StackedDrg64GiBV1 | StackedDrg64GiBV1_1 | StackedDrg64GiBV1_1_Ni => {
+                RegisteredPoStProof::StackedDrgWindow64GiBV1
+            }
+        }
+    }
+}
+
+impl From<RegisteredPoStProof> for RegisteredSealProof {
+    /// Maps a PoSt proof back to the seal proof run on sectors of the same
+    /// size.
+    fn from(other: RegisteredPoStProof) -> Self {
+        use RegisteredPoStProof::*;
+
+        match other {
+            StackedDrgWinning1KiBV1 | StackedDrgWindow1KiBV1 => {
+                RegisteredSealProof::StackedDrg1KiBV1
+            }
+            StackedDrgWinning16MiBV1 | StackedDrgWindow16MiBV1 => {
+                RegisteredSealProof::StackedDrg16MiBV1
+            }
+            StackedDrgWinning256MiBV1 | StackedDrgWindow256MiBV1 => {
+                RegisteredSealProof::StackedDrg256MiBV1
+            }
+            StackedDrgWinning1GiBV1 | StackedDrgWindow1GiBV1 => {
+                RegisteredSealProof::StackedDrg1GiBV1
+            }
+            StackedDrgWinning32GiBV1 | StackedDrgWindow32GiBV1 => {
+                RegisteredSealProof::StackedDrg32GiBV1
+            }
+            StackedDrgWinning2KiBV1 | StackedDrgWindow2KiBV1 => {
+                RegisteredSealProof::StackedDrg2KiBV1
+            }
+            StackedDrgWinning4KiBV1 | StackedDrgWindow4KiBV1 => {
+                RegisteredSealProof::StackedDrg4KiBV1
+            }
+            StackedDrgWinning16KiBV1 | StackedDrgWindow16KiBV1 => {
+                RegisteredSealProof::StackedDrg16KiBV1
+            }
+            StackedDrgWinning32KiBV1 | StackedDrgWindow32KiBV1 => {
+                RegisteredSealProof::StackedDrg32KiBV1
+            }
+            StackedDrgWinning8MiBV1 | StackedDrgWindow8MiBV1 => {
+                RegisteredSealProof::StackedDrg8MiBV1
+            }
+            StackedDrgWinning512MiBV1 | StackedDrgWindow512MiBV1 => {
+                RegisteredSealProof::StackedDrg512MiBV1
+            }
+            StackedDrgWinning64GiBV1 | StackedDrgWindow64GiBV1 => {
+                RegisteredSealProof::StackedDrg64GiBV1
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn porep_id_encodes_sector_size_and_version_flags() {
+        let sector_size: u64 = RegisteredSealProof::StackedDrg32GiBV1.sector_size().0;
+
+        let v1 = RegisteredSealProof::StackedDrg32GiBV1.porep_id();
+        assert_eq!(&v1[..8], &sector_size.to_le_bytes());
+        assert_eq!(v1[8], 0, "V1 porep_id must not set the V1_1 flag byte");
+        assert_eq!(v1[9], 0, "interactive porep_id must not set the NI flag byte");
+        assert!(v1[10..].iter().all(|&b| b == 0));
+
+        let v1_1 = RegisteredSealProof::StackedDrg32GiBV1_1.porep_id();
+        assert_eq!(&v1_1[..8], &sector_size.to_le_bytes());
+        assert_eq!(v1_1[8], 1, "V1_1 porep_id must set the V1_1 flag byte");
+        assert_eq!(v1_1[9], 0);
+
+        let ni = RegisteredSealProof::StackedDrg32GiBV1_1_Ni.porep_id();
+        assert_eq!(&ni[..8], &sector_size.to_le_bytes());
+        assert_eq!(ni[8], 1, "NI porep_id is built on the V1_1 graph construction");
+        assert_eq!(ni[9], 1, "NI porep_id must set the NI flag byte");
+    }
+
+    #[test]
+    fn registered_seal_proof_code_round_trips() {
+        let variants = [
+            RegisteredSealProof::StackedDrg1KiBV1,
+            RegisteredSealProof::StackedDrg32GiBV1,
+            RegisteredSealProof::StackedDrg32GiBV1_1,
+            RegisteredSealProof::StackedDrg64GiBV1_1_Ni,
+        ];
+
+        for variant in variants {
+            let code = variant.to_u64();
+            assert_eq!(RegisteredSealProof::from_u64(code), Some(variant));
+        }
+
+        assert_eq!(RegisteredSealProof::StackedDrg1KiBV1.to_u64(), 0);
+        assert_eq!(RegisteredSealProof::StackedDrg64GiBV1_1_Ni.to_u64(), 35);
+        assert_eq!(RegisteredSealProof::from_u64(u64::MAX), None);
+    }
+
+    #[test]
+    fn registered_post_proof_code_round_trips() {
+        let variants = [
+            RegisteredPoStProof::StackedDrgWinning1KiBV1,
+            RegisteredPoStProof::StackedDrgWindow64GiBV1,
+        ];
+
+        for variant in variants {
+            let code = variant.to_u64();
+            assert_eq!(RegisteredPoStProof::from_u64(code), Some(variant));
+        }
+
+        assert_eq!(RegisteredPoStProof::from_u64(u64::MAX), None);
+    }
 }
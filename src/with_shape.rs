@@ -0,0 +1,63 @@
+/// Dispatches on a sector size to the `SectorShape*` Merkle-tree type that
+/// proofs of that size are built over, then evaluates `$f::<Tree>($($args),*)`
+/// with `Tree` bound to that type.
+///
+/// This centralizes the sector-size-to-shape matching that would otherwise
+/// need to be duplicated at every call site that needs a concrete,
+/// monomorphized `MerkleTreeTrait` rather than just a `PoRepConfig`/
+/// `PoStConfig` value.
+macro_rules! with_shape {
+    ($size:expr, $f:ident) => {
+        with_shape!($size, $f,)
+    };
+    ($size:expr, $f:ident, $($args:expr),*) => {
+        match $size {
+            filecoin_proofs_v1::constants::SECTOR_SIZE_ONE_KIB => {
+                $f::<filecoin_proofs_v1::constants::SectorShapeOneKiB>($($args),*)
+            }
+            filecoin_proofs_v1::constants::SECTOR_SIZE_2_KIB => {
+                $f::<filecoin_proofs_v1::constants::SectorShape2KiB>($($args),*)
+            }
+            filecoin_proofs_v1::constants::SECTOR_SIZE_4_KIB => {
+                $f::<filecoin_proofs_v1::constants::SectorShape4KiB>($($args),*)
+            }
+            filecoin_proofs_v1::constants::SECTOR_SIZE_16_KIB => {
+                $f::<filecoin_proofs_v1::constants::SectorShape16KiB>($($args),*)
+            }
+            filecoin_proofs_v1::constants::SECTOR_SIZE_32_KIB => {
+                $f::<filecoin_proofs_v1::constants::SectorShape32KiB>($($args),*)
+            }
+            filecoin_proofs_v1::constants::SECTOR_SIZE_8_MIB => {
+                $f::<filecoin_proofs_v1::constants::SectorShape8MiB>($($args),*)
+            }
+            filecoin_proofs_v1::constants::SECTOR_SIZE_16_MIB => {
+                $f::<filecoin_proofs_v1::constants::SectorShape16MiB>($($args),*)
+            }
+            filecoin_proofs_v1::constants::SECTOR_SIZE_256_MIB => {
+                $f::<filecoin_proofs_v1::constants::SectorShape256MiB>($($args),*)
+            }
+            filecoin_proofs_v1::constants::SECTOR_SIZE_512_MIB => {
+                $f::<filecoin_proofs_v1::constants::SectorShape512MiB>($($args),*)
+            }
+            filecoin_proofs_v1::constants::SECTOR_SIZE_1_GIB => {
+                $f::<filecoin_proofs_v1::constants::SectorShape1GiB>($($args),*)
+            }
+            filecoin_proofs_v1::constants::SECTOR_SIZE_32_GIB => {
+                $f::<filecoin_proofs_v1::constants::SectorShape32GiB>($($args),*)
+            }
+            filecoin_proofs_v1::constants::SECTOR_SIZE_64_GIB => {
+                $f::<filecoin_proofs_v1::constants::SectorShape64GiB>($($args),*)
+            }
+            _ => panic!("unsupported sector size: {}", $size),
+        }
+    };
+}
+
+/// `self`-flavored convenience wrapper around [`with_shape`]: dispatches on
+/// `$self.sector_size()` rather than a sector size the caller already has in
+/// hand.
+macro_rules! self_shape {
+    ($self:expr, $f:ident $(, $args:expr)*) => {
+        with_shape!(u64::from($self.sector_size()), $f $(, $args)*)
+    };
+}